@@ -0,0 +1,72 @@
+//! Compact fuzzy subsequence matcher backing the attrset/list filter in
+//! [`crate::model::ListData`]. Not a general-purpose fuzzy finder: it only
+//! needs to rank short attribute/list-index names against a typed query.
+
+const MATCH_BASE_SCORE: i64 = 16;
+const SEPARATOR_BONUS: i64 = 8;
+const FIRST_CHAR_BONUS: i64 = 8;
+const GAP_PENALTY: i64 = 1;
+
+fn is_separator(c: char) -> bool {
+	matches!(c, '.' | '-' | '_')
+}
+
+/// Scores `candidate` as a case-insensitive subsequence match against
+/// `query`. Returns `None` if `candidate` doesn't contain every character of
+/// `query` in order. Higher is a better match; an empty query matches
+/// everything with a score of `0`.
+pub fn score(query: &str, candidate: &str) -> Option<i64> {
+	if query.is_empty() {
+		return Some(0);
+	}
+
+	let query: Vec<char> = query.to_lowercase().chars().collect();
+	let candidate_chars: Vec<char> = candidate.chars().collect();
+	let candidate_lower: Vec<char> = candidate.to_lowercase().chars().collect();
+
+	let mut total = 0i64;
+	let mut query_idx = 0;
+	let mut last_match: Option<usize> = None;
+
+	for (i, c) in candidate_lower.iter().enumerate() {
+		if query_idx >= query.len() {
+			break;
+		}
+		if *c != query[query_idx] {
+			continue;
+		}
+
+		let mut char_score = MATCH_BASE_SCORE;
+		if i == 0 {
+			char_score += FIRST_CHAR_BONUS;
+		} else if is_separator(candidate_chars[i - 1]) {
+			char_score += SEPARATOR_BONUS;
+		}
+		if let Some(last) = last_match {
+			char_score -= GAP_PENALTY * (i - last - 1) as i64;
+		}
+
+		total += char_score;
+		last_match = Some(i);
+		query_idx += 1;
+	}
+
+	(query_idx == query.len()).then_some(total)
+}
+
+#[test]
+fn rejects_non_subsequences() {
+	assert!(score("xyz", "foobar").is_none());
+}
+
+#[test]
+fn prefers_separator_and_first_char_matches_over_mid_word_gaps() {
+	let after_separator = score("fb", "foo.bar").unwrap();
+	let mid_word = score("fb", "fabribar").unwrap();
+	assert!(after_separator > mid_word);
+}
+
+#[test]
+fn empty_query_matches_everything_with_zero_score() {
+	assert_eq!(score("", "anything"), Some(0));
+}
@@ -0,0 +1,140 @@
+use std::path::Path;
+
+use once_cell::sync::Lazy;
+use ratatui::{
+	style::{Color, Modifier, Style},
+	text::{Line, Span, Text},
+};
+use syntect::{
+	easy::HighlightLines,
+	highlighting::{FontStyle, Theme, ThemeSet},
+	parsing::{SyntaxReference, SyntaxSet},
+	util::LinesWithEndings,
+};
+
+static SYNTAX_SET: Lazy<SyntaxSet> = Lazy::new(build_syntax_set);
+static THEME_SET: Lazy<ThemeSet> = Lazy::new(ThemeSet::load_defaults);
+
+/// `syntect`'s bundled defaults don't include Nix, so extend them with the
+/// grammar checked into `assets/syntaxes`. Falling back to the defaults alone
+/// if that folder can't be read means a packaging mistake degrades Nix
+/// previews to plain text rather than crashing the app.
+fn build_syntax_set() -> SyntaxSet {
+	let mut builder = SyntaxSet::load_defaults_newlines().into_builder();
+	let nix_syntax_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/syntaxes");
+	if let Err(err) = builder.add_from_folder(&nix_syntax_dir, true) {
+		tracing::warn!("failed to load bundled Nix syntax from {nix_syntax_dir:?}: {err}");
+	}
+	builder.build()
+}
+
+const FALLBACK_THEME: &str = "base16-ocean.dark";
+
+/// The languages the preview pane knows how to sniff and highlight. Detection
+/// is content-based for `String` values (they have no extension to go on)
+/// and extension-based for `Path` values pointing at store paths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentKind {
+	Nix,
+	Json,
+	Shell,
+}
+
+impl ContentKind {
+	fn extension(self) -> &'static str {
+		match self {
+			ContentKind::Nix => "nix",
+			ContentKind::Json => "json",
+			ContentKind::Shell => "sh",
+		}
+	}
+
+	pub fn detect_string(content: &str) -> Option<ContentKind> {
+		let trimmed = content.trim_start();
+		if !content.contains('\n') {
+			return None;
+		}
+		if (trimmed.starts_with('{') || trimmed.starts_with('['))
+			&& serde_json::from_str::<serde_json::Value>(content).is_ok()
+		{
+			return Some(ContentKind::Json);
+		}
+		if trimmed.starts_with("#!") && (trimmed.contains("sh") || trimmed.contains("bash")) {
+			return Some(ContentKind::Shell);
+		}
+		if trimmed.contains("mkDerivation") || trimmed.contains("with import") || trimmed.contains("lib.mk") {
+			return Some(ContentKind::Nix);
+		}
+		None
+	}
+
+	pub fn detect_path(path: &str) -> Option<ContentKind> {
+		if path.ends_with(".nix") {
+			Some(ContentKind::Nix)
+		} else if path.ends_with(".sh") {
+			Some(ContentKind::Shell)
+		} else {
+			None
+		}
+	}
+}
+
+/// A loaded `syntect` syntax + theme pair, held behind `once_cell` statics so
+/// the (fairly slow) default syntax/theme sets are only ever parsed once.
+pub struct SyntectHighlighter {
+	syntax: &'static SyntaxReference,
+	theme: &'static Theme,
+}
+
+impl SyntectHighlighter {
+	pub fn for_content(kind: ContentKind, theme_name: &str) -> Option<Self> {
+		let syntax = SYNTAX_SET.find_syntax_by_extension(kind.extension())?;
+		let theme = THEME_SET
+			.themes
+			.get(theme_name)
+			.or_else(|| THEME_SET.themes.get(FALLBACK_THEME))?;
+		Some(SyntectHighlighter { syntax, theme })
+	}
+
+	/// Highlights `content`, only materializing styled lines in
+	/// `start..end` (the visible viewport). Earlier lines are still fed
+	/// through the highlighter so its line-sequential state (open strings,
+	/// comments, …) is correct by the time the viewport is reached, but their
+	/// styled output is discarded rather than allocated.
+	pub fn highlight_viewport(&self, content: &str, start: usize, end: usize) -> Text<'static> {
+		let mut highlighter = HighlightLines::new(self.syntax, self.theme);
+		let mut lines = Vec::new();
+
+		for (i, line) in LinesWithEndings::from(content).enumerate() {
+			if i >= end {
+				break;
+			}
+			let Ok(ranges) = highlighter.highlight_line(line, &SYNTAX_SET) else {
+				break;
+			};
+			if i < start {
+				continue;
+			}
+			lines.push(Line::from(
+				ranges
+					.into_iter()
+					.map(|(style, text)| Span::styled(text.trim_end_matches('\n').to_string(), to_ratatui_style(style)))
+					.collect::<Vec<_>>(),
+			));
+		}
+
+		Text::from(lines)
+	}
+}
+
+fn to_ratatui_style(style: syntect::highlighting::Style) -> Style {
+	let mut ratatui_style =
+		Style::default().fg(Color::Rgb(style.foreground.r, style.foreground.g, style.foreground.b));
+	if style.font_style.contains(FontStyle::BOLD) {
+		ratatui_style = ratatui_style.add_modifier(Modifier::BOLD);
+	}
+	if style.font_style.contains(FontStyle::ITALIC) {
+		ratatui_style = ratatui_style.add_modifier(Modifier::ITALIC);
+	}
+	ratatui_style
+}
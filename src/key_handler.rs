@@ -0,0 +1,218 @@
+use std::{
+	sync::Arc,
+	thread,
+	time::{Duration, Instant},
+};
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use kanal::Sender;
+use parking_lot::RwLock;
+
+use crate::model::{BrowserPath, InputState, Message, Model};
+
+/// How long a partially-typed multi-key sequence (e.g. the `g` in `g g`) is
+/// kept around waiting for its next key before it's dropped.
+const PENDING_KEY_TIMEOUT: Duration = Duration::from_millis(600);
+
+pub fn register_key_handler(tx: &Sender<Message>, model: Arc<RwLock<Model>>) {
+	let tx = tx.clone();
+	thread::spawn(move || loop {
+		if event::poll(Duration::from_millis(100)).unwrap_or(false) {
+			if let Ok(Event::Key(key)) = event::read() {
+				let msg = translate_key_event(&mut model.write(), key);
+				if let Some(msg) = msg {
+					if tx.send(msg).is_err() {
+						break;
+					}
+				}
+			}
+		}
+	});
+}
+
+fn translate_key_event(model: &mut Model, key: KeyEvent) -> Option<Message> {
+	if matches!(model.search_input, InputState::Active(_)) {
+		return Some(
+			lookup_mode_action(model, "search", key).unwrap_or(match key.code {
+				KeyCode::Esc => Message::SearchExit,
+				KeyCode::Enter => Message::SearchEnter,
+				_ => Message::SearchInput(key),
+			}),
+		);
+	}
+	if matches!(model.path_navigator_input, InputState::Active(_)) {
+		return Some(
+			lookup_mode_action(model, "navigator", key).unwrap_or(match key.code {
+				KeyCode::Esc => Message::NavigatorExit,
+				KeyCode::Enter => Message::NavigatorEnter,
+				_ => Message::NavigatorInput(key),
+			}),
+		);
+	}
+	if matches!(model.new_bookmark_input, InputState::Active(_)) {
+		return Some(
+			lookup_mode_action(model, "bookmark", key).unwrap_or(match key.code {
+				KeyCode::Esc => Message::BookmarkInputExit,
+				KeyCode::Enter => Message::BookmarkInputEnter,
+				_ => Message::BookmarkInput(key),
+			}),
+		);
+	}
+	if matches!(model.filter_input, InputState::Active(_)) {
+		return Some(match key.code {
+			KeyCode::Esc | KeyCode::Enter => Message::FilterExit,
+			_ => Message::FilterInput(key),
+		});
+	}
+
+	resolve_default_key(model, key)
+}
+
+/// Looks up `key` as a single-key binding in `mode`'s table. These modes are
+/// text-entry boxes, so (unlike `"default"`) they don't support multi-key
+/// sequences.
+fn lookup_mode_action(model: &Model, mode: &str, key: KeyEvent) -> Option<Message> {
+	model
+		.config
+		.keybindings
+		.get(mode)
+		.and_then(|bindings| bindings.get(&key_to_string(key)))
+		.and_then(|action| parse_message(action))
+}
+
+/// Resolves a key while browsing normally, buffering keys in
+/// `model.pending_keys` so multi-key `config.keybindings["default"]` entries
+/// like `"g g"` can match, and falling back to `default_action_for_key` once
+/// it's clear no configured binding (complete or in-progress) applies.
+fn resolve_default_key(model: &mut Model, key: KeyEvent) -> Option<Message> {
+	if model.pending_since.is_some_and(|since| since.elapsed() > PENDING_KEY_TIMEOUT) {
+		model.pending_keys.clear();
+	}
+	model.pending_keys.push(key);
+	model.pending_since = Some(Instant::now());
+
+	let pending_spec = model.pending_keys.iter().map(|k| key_to_string(*k)).collect::<Vec<_>>().join(" ");
+
+	if let Some(bindings) = model.config.keybindings.get("default") {
+		if let Some(action) = bindings.get(&pending_spec) {
+			model.pending_keys.clear();
+			model.pending_since = None;
+			return parse_message(action);
+		}
+		let prefix = format!("{pending_spec} ");
+		if bindings.keys().any(|spec| spec.starts_with(&prefix)) {
+			// A longer binding could still match; wait for the next key.
+			return None;
+		}
+	}
+
+	let pending = std::mem::take(&mut model.pending_keys);
+	model.pending_since = None;
+	if pending.len() > 1 {
+		// An unmatched multi-key sequence has no single-key built-in fallback.
+		return None;
+	}
+
+	default_action_for_key(key).and_then(parse_message)
+}
+
+/// Renders a key into the string form used by `config.keybindings`, e.g.
+/// `"ctrl-d"`, `"g"`, `"/"`. Shift isn't included for `Char` keys since the
+/// character's case already encodes it.
+fn key_to_string(key: KeyEvent) -> String {
+	let mut parts = Vec::new();
+	if key.modifiers.contains(KeyModifiers::CONTROL) {
+		parts.push("ctrl".to_string());
+	}
+	if key.modifiers.contains(KeyModifiers::ALT) {
+		parts.push("alt".to_string());
+	}
+
+	parts.push(match key.code {
+		KeyCode::Char(c) => c.to_string(),
+		KeyCode::Enter => "enter".to_string(),
+		KeyCode::Esc => "esc".to_string(),
+		KeyCode::Backspace => "backspace".to_string(),
+		KeyCode::Tab => "tab".to_string(),
+		KeyCode::Left => "left".to_string(),
+		KeyCode::Right => "right".to_string(),
+		KeyCode::Up => "up".to_string(),
+		KeyCode::Down => "down".to_string(),
+		KeyCode::PageUp => "pageup".to_string(),
+		KeyCode::PageDown => "pagedown".to_string(),
+		KeyCode::Home => "home".to_string(),
+		KeyCode::End => "end".to_string(),
+		other => format!("{other:?}").to_lowercase(),
+	});
+
+	parts.join("-")
+}
+
+fn default_action_for_key(key: KeyEvent) -> Option<&'static str> {
+	match (key.code, key.modifiers) {
+		(KeyCode::Char('q'), _) => Some("Quit"),
+		(KeyCode::Char('c'), KeyModifiers::CONTROL) => Some("Quit"),
+		(KeyCode::Char('r'), _) => Some("Refresh"),
+		(KeyCode::Down, _) | (KeyCode::Char('j'), _) => Some("ListDown"),
+		(KeyCode::Up, _) | (KeyCode::Char('k'), _) => Some("ListUp"),
+		(KeyCode::PageDown, _) => Some("PageDown"),
+		(KeyCode::PageUp, _) => Some("PageUp"),
+		(KeyCode::Enter, _) | (KeyCode::Right, _) | (KeyCode::Char('l'), _) => Some("EnterItem"),
+		(KeyCode::Backspace, _) | (KeyCode::Left, _) | (KeyCode::Char('h'), _) => Some("Back"),
+		(KeyCode::Char('n'), _) => Some("SearchNext"),
+		(KeyCode::Char('N'), _) => Some("SearchPrev"),
+		(KeyCode::Char('/'), _) => Some("FilterEnter"),
+		(KeyCode::Char(':'), _) => Some("NavigatorEnter"),
+		(KeyCode::Char('b'), _) => Some("BookmarkInputEnter"),
+		(KeyCode::Char('d'), _) => Some("DeleteBookmark"),
+		(KeyCode::Char('s'), _) => Some("ToggleSort"),
+		(KeyCode::Char(' '), _) => Some("ToggleSelect"),
+		(KeyCode::Char('a'), _) => Some("SelectAll"),
+		(KeyCode::Char('u'), _) => Some("ClearSelection"),
+		(KeyCode::Char('y'), _) => Some("ExportSelection"),
+		_ => None,
+	}
+}
+
+/// Parses a single newline-delimited textual command (as written to the
+/// session's `msg_in` pipe) into a [`Message`]. Shared with the keymaps above
+/// so built-in/configured keybindings and pipe-driven automation resolve to
+/// the same action vocabulary.
+pub fn parse_message(line: &str) -> Option<Message> {
+	let line = line.trim();
+	let (cmd, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+	let rest = rest.trim();
+
+	match cmd {
+		"EnterItem" => Some(Message::EnterItem),
+		"Back" => Some(Message::Back),
+		"Quit" => Some(Message::Quit),
+		"Refresh" => Some(Message::Refresh),
+		"ListUp" => Some(Message::ListUp),
+		"ListDown" => Some(Message::ListDown),
+		"PageUp" => Some(Message::PageUp),
+		"PageDown" => Some(Message::PageDown),
+		"SearchEnter" => Some(Message::SearchEnter),
+		"SearchExit" => Some(Message::SearchExit),
+		"SearchNext" => Some(Message::SearchNext),
+		"SearchPrev" => Some(Message::SearchPrev),
+		"NavigatorEnter" => Some(Message::NavigatorEnter),
+		"NavigatorExit" => Some(Message::NavigatorExit),
+		"NavigatorNext" => Some(Message::NavigatorNext),
+		"NavigatorPrev" => Some(Message::NavigatorPrev),
+		"BookmarkInputEnter" => Some(Message::BookmarkInputEnter),
+		"BookmarkInputExit" => Some(Message::BookmarkInputExit),
+		"DeleteBookmark" => Some(Message::DeleteBookmark),
+		"FilterEnter" => Some(Message::FilterEnter),
+		"FilterExit" => Some(Message::FilterExit),
+		"ToggleSort" => Some(Message::ToggleSort),
+		"ToggleSelect" => Some(Message::ToggleSelect),
+		"SelectAll" => Some(Message::SelectAll),
+		"ClearSelection" => Some(Message::ClearSelection),
+		"ExportSelection" => Some(Message::ExportSelection),
+		"CreateBookmark" if !rest.is_empty() => Some(Message::CreateBookmark(Some(rest.to_string()))),
+		"CreateBookmark" => Some(Message::CreateBookmark(None)),
+		"FocusPath" if !rest.is_empty() => Some(Message::FocusPath(BrowserPath::from(rest.to_string()))),
+		_ => None,
+	}
+}
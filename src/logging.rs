@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use directories::ProjectDirs;
+use tracing_error::ErrorLayer;
+use tracing_subscriber::{filter::EnvFilter, layer::SubscriberExt, util::SubscriberInitExt, Layer};
+
+pub fn project_directory() -> Option<ProjectDirs> {
+	ProjectDirs::from("dev", "laughingman-hass", "nix-inspect")
+}
+
+pub fn initialize_logging() -> color_eyre::Result<()> {
+	let directory = project_directory()
+		.map(|d| d.data_local_dir().to_path_buf())
+		.unwrap_or_else(|| PathBuf::from(".data"));
+	std::fs::create_dir_all(&directory)?;
+
+	let log_file = std::fs::File::create(directory.join("nix-inspect.log"))?;
+
+	let file_subscriber = tracing_subscriber::fmt::layer()
+		.with_file(true)
+		.with_line_number(true)
+		.with_writer(log_file)
+		.with_target(false)
+		.with_ansi(false)
+		.with_filter(EnvFilter::from_default_env());
+
+	tracing_subscriber::registry()
+		.with(file_subscriber)
+		.with(ErrorLayer::default())
+		.init();
+
+	Ok(())
+}
@@ -1,4 +1,5 @@
 use std::{
+	collections::HashMap,
 	env,
 	fs::{create_dir_all, File},
 	io::Read,
@@ -12,6 +13,7 @@ use key_handler::register_key_handler;
 use logging::{initialize_logging, project_directory};
 use model::{Bookmark, BrowserPath, BrowserStack, BrowserStackItem, Message, Model, RunningState};
 use parking_lot::RwLock;
+use pipe::Pipe;
 use ratatui::widgets::ListState;
 use serde::{Deserialize, Serialize};
 use update::UpdateContext;
@@ -20,17 +22,44 @@ use workers::WorkerHost;
 
 use crate::view::ViewData;
 
+pub mod fuzzy;
+pub mod highlight;
 pub mod key_handler;
 pub mod logging;
 pub mod model;
+pub mod pipe;
 pub mod tui;
 pub mod update;
 pub mod view;
+pub mod watcher;
 pub mod workers;
 
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
 pub struct Config {
 	bookmarks: Vec<Bookmark>,
+	/// Name of the `syntect` theme used to highlight the preview pane, e.g.
+	/// `"base16-ocean.dark"`. Falls back to that same theme when unset or
+	/// unrecognized.
+	#[serde(default)]
+	theme: String,
+	/// Mode name (`"default"`, `"search"`, `"navigator"`, `"bookmark"`) to key
+	/// string (e.g. `"ctrl-d"`, `"g g"`, `"/"`) to the name of a `key_handler`
+	/// action, consulted before the built-in keymap so bindings can be
+	/// rebound without recompiling. Key strings for multi-key sequences are
+	/// space-separated, in the order the keys are pressed.
+	#[serde(default)]
+	keybindings: HashMap<String, HashMap<String, String>>,
+}
+
+/// A starter `default`-mode keymap written into a freshly bootstrapped
+/// `config.json`, so the `keybindings` feature is discoverable without
+/// reading the source. Anything not listed here still falls back to
+/// `key_handler::default_action_for_key`.
+fn default_keybindings() -> HashMap<String, HashMap<String, String>> {
+	let mut default_mode = HashMap::new();
+	default_mode.insert("ctrl-d".to_string(), "PageDown".to_string());
+	default_mode.insert("ctrl-u".to_string(), "PageUp".to_string());
+	HashMap::from([("default".to_string(), default_mode)])
 }
 
 #[derive(Parser, Debug)]
@@ -52,26 +81,46 @@ pub fn find_in_nix_path() -> color_eyre::Result<String> {
 		.to_string())
 }
 
-fn load_config(args: &Args) -> color_eyre::Result<String> {
+/// The Nix expression to evaluate, plus the on-disk files/directories that
+/// back it so the watcher subsystem knows what to watch for changes.
+struct LoadedConfig {
+	expr: String,
+	watch_paths: Vec<PathBuf>,
+}
+
+fn load_config(args: &Args) -> color_eyre::Result<LoadedConfig> {
 	if let Some(expr) = &args.expr {
-		Ok(expr.to_string())
+		Ok(LoadedConfig {
+			expr: expr.to_string(),
+			watch_paths: vec![],
+		})
 	} else if let Some(path) = &args.path {
 		let is_file = Path::new(path).is_file();
 		let is_flake =
 			is_file && path.ends_with("flake.nix") || Path::new(path).join("flake.nix").exists();
 
-		Ok(if is_flake {
+		let expr = if is_flake {
 			format!(r#"builtins.getFlake "{path}""#)
 		} else {
 			format!("(import <nixpkgs/nixos>) {{ system = builtins.currentSystem; configuration = import {}; }}", path)
+		};
+		Ok(LoadedConfig {
+			expr,
+			watch_paths: vec![PathBuf::from(path)],
 		})
 	} else {
 		let etc_nixos_flake = Path::new("/etc/nixos/flake.nix");
 		if etc_nixos_flake.exists() {
-			Ok(r#"builtins.getFlake "/etc/nixos""#.to_string())
+			Ok(LoadedConfig {
+				expr: r#"builtins.getFlake "/etc/nixos""#.to_string(),
+				watch_paths: vec![PathBuf::from("/etc/nixos")],
+			})
 		} else {
 			let path = find_in_nix_path().unwrap_or("/etc/nixos".to_string());
-			Ok(format!("(import <nixpkgs/nixos>) {{ system = builtins.currentSystem; configuration = import {}; }}", path))
+			Ok(LoadedConfig {
+				expr: format!("(import <nixpkgs/nixos>) {{ system = builtins.currentSystem; configuration = import {}; }}", path),
+				watch_paths: vec![PathBuf::from(path)],
+			})
 		}
 	}
 }
@@ -113,6 +162,8 @@ fn main() -> color_eyre::Result<()> {
 					path: BrowserPath::from(user_path.to_string()),
 				},
 			],
+			theme: "base16-ocean.dark".to_string(),
+			keybindings: default_keybindings(),
 		};
 		create_dir_all(config_path.parent().unwrap())?;
 		let x = serde_json::to_string_pretty(&config)?;
@@ -122,10 +173,10 @@ fn main() -> color_eyre::Result<()> {
 	};
 
 	let args = Args::parse();
-	let expr = load_config(&args)?;
-	tracing::debug!("{}", expr);
+	let loaded_config = load_config(&args)?;
+	tracing::debug!("{}", loaded_config.expr);
 
-	let worker_host = WorkerHost::new(expr);
+	let worker_host = WorkerHost::new(loaded_config.expr);
 	let model = Arc::new(RwLock::new(Model {
 		running_state: RunningState::Running,
 		visit_stack: BrowserStack(vec![BrowserStackItem::Root]),
@@ -135,13 +186,18 @@ fn main() -> color_eyre::Result<()> {
 		..Default::default()
 	}));
 
+	let session_pipe = Pipe::create()?;
+
 	let mut update_context = UpdateContext {
 		req_tx: worker_host.tx.clone(),
 		config_path,
+		session_path: session_pipe.directory.clone(),
 	};
 
 	let (tx, rx) = kanal::unbounded::<Message>();
 	register_key_handler(&tx, Arc::clone(&model));
+	session_pipe.watch(tx.clone());
+	watcher::watch(loaded_config.watch_paths, tx.clone());
 
 	{
 		let worker_rx = worker_host.rx.clone();
@@ -155,6 +211,8 @@ fn main() -> color_eyre::Result<()> {
 		});
 	}
 
+	let mut focused_path = None;
+
 	while model.read().running_state != RunningState::Stopped {
 		// Render the current view
 		let mut view_data: ViewData = ViewData::default();
@@ -162,6 +220,13 @@ fn main() -> color_eyre::Result<()> {
 			view_data = view(&model.read(), f);
 		})?;
 
+		let current_focus = model.read().visit_stack.current().cloned();
+		if current_focus != focused_path {
+			let expr = current_focus.as_ref().map(|p| p.to_expr()).unwrap_or_default();
+			session_pipe.write_focus(&expr)?;
+			focused_path = current_focus;
+		}
+
 		let mut current_msg = Some(rx.recv()?);
 
 		// Process updates as long as they return a non-None message
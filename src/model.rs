@@ -1,14 +1,21 @@
 use std::{
 	collections::HashMap,
 	fmt,
-	ops::{Deref, DerefMut},
+	ops::{Deref, DerefMut, Range},
+	time::Instant,
 };
 
 use crossterm::event::{KeyCode, KeyEvent};
+use indexmap::IndexSet;
 use ratatui::{text::Text, widgets::ListState};
 use serde::{Deserialize, Deserializer, Serialize};
 
-use crate::{workers::NixValue, Config};
+use crate::{
+	fuzzy,
+	highlight::{ContentKind, SyntectHighlighter},
+	workers::NixValue,
+	Config,
+};
 
 #[derive(Default, Debug)]
 pub struct Model {
@@ -21,13 +28,29 @@ pub struct Model {
 
 	pub visit_stack: BrowserStack,
 
+	/// Paths marked across the tree, exported as a set via `ExportSelection`.
+	pub selection: IndexSet<BrowserPath>,
+	/// Set when `ExportSelection` fires before every marked path has resolved
+	/// data, so the export can be retried once `Message::Data` fills in the
+	/// last of them instead of writing `null` for anything not yet fetched.
+	pub pending_export: bool,
+
 	pub search_input: InputState,
 	pub path_navigator_input: InputState,
 	pub new_bookmark_input: InputState,
+	/// Drives the filter box for the currently focused `ListData`.
+	pub filter_input: InputState,
 
 	/// TODO: things that the architecture doesnt handle all that well
 	pub prev_tab_completion: Option<String>,
 
+	/// Keys typed so far toward a multi-key `config.keybindings` entry (e.g.
+	/// `"g g"`), along with when the most recent of them arrived. Cleared once a
+	/// binding resolves, no binding can still match, or `pending_since` is
+	/// older than `key_handler::PENDING_KEY_TIMEOUT`.
+	pub pending_keys: Vec<KeyEvent>,
+	pub pending_since: Option<Instant>,
+
 	pub root_view_state: ListState,
 	pub bookmark_view_state: ListState,
 	pub recents_view_state: ListState,
@@ -54,7 +77,8 @@ impl Model {
 		while let Some(parent) = path.parent() {
 			new_stack.push(BrowserStackItem::BrowserPath(parent.clone()));
 			if let Some(PathData::List(list)) = self.path_data.get_mut(&parent) {
-				if let Some(pos) = list.list.iter().position(|x| x == path.0.last().unwrap()) {
+				let child_name = path.0.last().unwrap();
+				if let Some(pos) = list.visible.iter().position(|&i| &list.list[i] == child_name) {
 					list.state.select(Some(pos));
 				}
 			}
@@ -96,6 +120,42 @@ impl PathDataMap {
 			_ => None,
 		})
 	}
+
+	/// Drops cached data for every path currently on `visit_stack` (not the
+	/// rest of the tree) and returns them, so a config-file change only pays
+	/// for re-fetching what's actually in view rather than everything
+	/// nix-inspect has ever visited.
+	pub fn invalidate(&mut self, visit_stack: &BrowserStack) -> Vec<BrowserPath> {
+		let paths: Vec<BrowserPath> = visit_stack
+			.iter()
+			.filter_map(|item| match item {
+				BrowserStackItem::BrowserPath(p) => Some(p.clone()),
+				_ => None,
+			})
+			.collect();
+		for path in &paths {
+			self.remove(path);
+		}
+		paths
+	}
+
+	/// Recomputes `visible` for the list at `path`, consulting already-fetched
+	/// children to decide attrset/list vs scalar for `SortMode::ByType`. A
+	/// child that hasn't been fetched yet is treated as a scalar.
+	pub fn recompute_visible_for(&mut self, path: &BrowserPath) {
+		let is_container: Vec<bool> = match self.get(path) {
+			Some(PathData::List(list)) => list
+				.list
+				.iter()
+				.map(|name| matches!(self.get(&path.child(name.clone())), Some(PathData::List(_))))
+				.collect(),
+			_ => return,
+		};
+
+		if let Some(PathData::List(list)) = self.get_mut(path) {
+			list.recompute_visible(&is_container);
+		}
+	}
 }
 
 #[derive(Debug, Default)]
@@ -140,6 +200,8 @@ pub enum Message {
 	TermEvent(crossterm::event::Event),
 	Data(BrowserPath, PathData),
 	CurrentPath(BrowserPath),
+	/// Jump straight to a path, as requested over the session's `msg_in` pipe.
+	FocusPath(BrowserPath),
 	Refresh,
 	PageDown,
 	PageUp,
@@ -152,7 +214,9 @@ pub enum Message {
 	BookmarkInputEnter,
 	BookmarkInputExit,
 	BookmarkInput(KeyEvent),
-	CreateBookmark,
+	/// `Some(display)` when the name was supplied directly (e.g. via the
+	/// `msg_in` pipe); `None` to use `Model::new_bookmark_input` instead.
+	CreateBookmark(Option<String>),
 	DeleteBookmark,
 	Back,
 	EnterItem,
@@ -162,6 +226,20 @@ pub enum Message {
 	SearchPrev,
 	NavigatorNext,
 	NavigatorPrev,
+	/// Open the fuzzy filter box for the currently focused list.
+	FilterEnter,
+	FilterInput(KeyEvent),
+	FilterExit,
+	/// Cycle `ListData::sort` on the currently focused list.
+	ToggleSort,
+	/// Mark or unmark the currently focused row.
+	ToggleSelect,
+	/// Mark every entry in the currently focused `ListData`.
+	SelectAll,
+	ClearSelection,
+	/// Write the marked paths' resolved values out to `selection_out` and the
+	/// system clipboard.
+	ExportSelection,
 	Quit,
 }
 
@@ -292,20 +370,107 @@ pub enum ListType {
 	Attrset,
 }
 
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum SortMode {
+	#[default]
+	None,
+	Alphabetical,
+	ByType,
+}
+
+impl SortMode {
+	pub fn next(&self) -> SortMode {
+		match self {
+			SortMode::None => SortMode::Alphabetical,
+			SortMode::Alphabetical => SortMode::ByType,
+			SortMode::ByType => SortMode::None,
+		}
+	}
+}
+
 #[derive(Debug, Clone)]
 pub struct ListData {
 	pub state: ListState,
 	pub list_type: ListType,
 	pub list: Vec<String>,
+	pub filter: Option<String>,
+	pub sort: SortMode,
+	/// Indices into `list` that should actually be shown, in display order.
+	/// Navigation (`select_next`/`select_prev`, paging) and `selected` all
+	/// operate on this rather than `list` directly.
+	pub visible: Vec<usize>,
 }
 
 impl ListData {
+	pub fn new(list_type: ListType, list: Vec<String>) -> Self {
+		let mut data = ListData {
+			state: ListState::default().with_selected(Some(0)),
+			list_type,
+			list,
+			filter: None,
+			sort: SortMode::default(),
+			visible: Vec::new(),
+		};
+		data.recompute_visible(&[]);
+		data
+	}
+
 	pub fn selected(&self, current_path: &BrowserPath) -> Option<BrowserPath> {
 		self.state
 			.selected()
-			.and_then(|i| self.list.get(i))
+			.and_then(|i| self.visible.get(i))
+			.and_then(|&idx| self.list.get(idx))
 			.map(|x| current_path.child(x.to_string()))
 	}
+
+	pub fn len(&self) -> usize {
+		self.visible.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.visible.is_empty()
+	}
+
+	/// Rebuilds `visible` from `list`, applying the active fuzzy filter (if
+	/// any) and `sort`. `is_container` is a parallel array to `list` used by
+	/// `SortMode::ByType` to group attrsets/lists ahead of scalars. The
+	/// currently selected entry (by name, not index) is carried over into the
+	/// new `visible`, so a child's data arriving doesn't reset the parent
+	/// list's cursor back to the top.
+	pub fn recompute_visible(&mut self, is_container: &[bool]) {
+		let selected_name =
+			self.state.selected().and_then(|i| self.visible.get(i)).and_then(|&idx| self.list.get(idx)).cloned();
+
+		let filtering = self.filter.as_deref().is_some_and(|q| !q.is_empty());
+
+		let mut visible: Vec<(usize, i64)> = match self.filter.as_deref() {
+			Some(query) if filtering => self
+				.list
+				.iter()
+				.enumerate()
+				.filter_map(|(i, name)| fuzzy::score(query, name).map(|score| (i, score)))
+				.collect(),
+			_ => self.list.iter().enumerate().map(|(i, _)| (i, 0)).collect(),
+		};
+
+		match self.sort {
+			SortMode::None if filtering => {
+				visible.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| self.list[a.0].cmp(&self.list[b.0])));
+			}
+			SortMode::None => {}
+			SortMode::Alphabetical => visible.sort_by(|a, b| self.list[a.0].cmp(&self.list[b.0])),
+			SortMode::ByType => visible.sort_by(|a, b| {
+				let a_container = is_container.get(a.0).copied().unwrap_or(false);
+				let b_container = is_container.get(b.0).copied().unwrap_or(false);
+				b_container.cmp(&a_container).then_with(|| self.list[a.0].cmp(&self.list[b.0]))
+			}),
+		}
+
+		self.visible = visible.into_iter().map(|(i, _)| i).collect();
+
+		let retained = selected_name.and_then(|name| self.visible.iter().position(|&i| self.list[i] == name));
+		self.state.select(retained.or_else(|| if self.visible.is_empty() { None } else { Some(0) }));
+	}
 }
 
 #[derive(Debug, Clone)]
@@ -353,16 +518,10 @@ impl From<NixValue> for PathData {
 			NixValue::String(s) => PathData::String(s),
 			NixValue::Path(p) => PathData::Path(p),
 			NixValue::Null => PathData::Null,
-			NixValue::Attrs(attrs) => PathData::List(ListData {
-				list_type: ListType::Attrset,
-				state: ListState::default().with_selected(Some(0)),
-				list: attrs,
-			}),
-			NixValue::List(size) => PathData::List(ListData {
-				list_type: ListType::List,
-				state: ListState::default().with_selected(Some(0)),
-				list: (0..size).map(|i| format!("{}", i)).collect(),
-			}),
+			NixValue::Attrs(attrs) => PathData::List(ListData::new(ListType::Attrset, attrs)),
+			NixValue::List(size) => {
+				PathData::List(ListData::new(ListType::List, (0..size).map(|i| format!("{}", i)).collect()))
+			}
 			NixValue::Function => PathData::Function,
 			NixValue::External => PathData::External,
 			NixValue::Error(e) => PathData::Error(e),
@@ -391,6 +550,29 @@ impl PathData {
 		}
 		.to_string()
 	}
+
+	/// Renders a syntax-highlighted preview for `String`/`Path` values whose
+	/// content sniffs as Nix, JSON, or shell, falling back to the plain
+	/// `Display` rendering otherwise. Only `visible_lines` are highlighted so
+	/// scrolling a large value stays responsive.
+	pub fn highlighted(&self, theme: &str, visible_lines: Range<usize>) -> Text<'static> {
+		let highlighted = match self {
+			PathData::String(value) => {
+				ContentKind::detect_string(value).map(|kind| (kind, value.clone()))
+			}
+			PathData::Path(path) => {
+				ContentKind::detect_path(path).zip(std::fs::read_to_string(path).ok())
+			}
+			_ => None,
+		};
+
+		highlighted
+			.and_then(|(kind, content)| {
+				SyntectHighlighter::for_content(kind, theme)
+					.map(|h| h.highlight_viewport(&content, visible_lines.start, visible_lines.end))
+			})
+			.unwrap_or_else(|| Text::raw(self.to_string()))
+	}
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -470,7 +652,9 @@ impl InputModel {
 }
 
 pub fn next(i: usize, len: usize) -> usize {
-	if i >= len - 1 {
+	if len == 0 {
+		0
+	} else if i >= len - 1 {
 		0
 	} else {
 		i + 1
@@ -482,7 +666,9 @@ pub fn select_next(list_state: &mut ListState, len: usize) {
 }
 
 pub fn prev(i: usize, len: usize) -> usize {
-	if i == 0 {
+	if len == 0 {
+		0
+	} else if i == 0 {
 		len - 1
 	} else {
 		i - 1
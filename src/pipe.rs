@@ -0,0 +1,87 @@
+use std::{
+	env, fs,
+	io::{BufRead, BufReader},
+	path::PathBuf,
+	thread,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use color_eyre::eyre::OptionExt;
+use kanal::Sender;
+use nix::{sys::stat::Mode, unistd::mkfifo};
+
+use crate::{key_handler::parse_message, logging::project_directory, model::Message};
+
+/// Env var spawned commands can read to find their way back to the session's
+/// pipes, e.g. `echo "FocusPath .foo.bar" > "$NIX_INSPECT_SESSION_PATH/msg_in"`.
+pub const SESSION_PATH_ENV: &str = "NIX_INSPECT_SESSION_PATH";
+
+/// The files a running session exposes for external scripting, mirroring
+/// xplr's `Pipe`: `msg_in` is a named pipe (FIFO) that accepts commands,
+/// `focus_out`/`selection_out` are plain files that report state back out.
+#[derive(Debug, Clone)]
+pub struct Pipe {
+	pub directory: PathBuf,
+	pub msg_in: PathBuf,
+	pub focus_out: PathBuf,
+	pub selection_out: PathBuf,
+}
+
+impl Pipe {
+	pub fn create() -> color_eyre::Result<Self> {
+		let runtime_dir = env::var_os("XDG_RUNTIME_DIR")
+			.map(PathBuf::from)
+			.or_else(|| project_directory().map(|d| d.cache_dir().to_path_buf()))
+			.ok_or_eyre("could not find a runtime directory to host the session pipes in")?;
+
+		let session_id = format!(
+			"{}-{}",
+			std::process::id(),
+			SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis()
+		);
+		let directory = runtime_dir.join("nix-inspect").join(session_id);
+		fs::create_dir_all(&directory)?;
+
+		let pipe = Pipe {
+			msg_in: directory.join("msg_in"),
+			focus_out: directory.join("focus_out"),
+			selection_out: directory.join("selection_out"),
+			directory,
+		};
+
+		mkfifo(&pipe.msg_in, Mode::S_IRWXU)?;
+		fs::File::create(&pipe.focus_out)?;
+		fs::File::create(&pipe.selection_out)?;
+
+		env::set_var(SESSION_PATH_ENV, &pipe.directory);
+
+		Ok(pipe)
+	}
+
+	/// Spawns a thread that watches `msg_in` for newline-delimited commands
+	/// and forwards them onto the same channel key events are sent on. A
+	/// FIFO yields EOF once its writer closes, so the file is reopened in a
+	/// loop rather than read once.
+	pub fn watch(&self, tx: Sender<Message>) {
+		let msg_in = self.msg_in.clone();
+		thread::spawn(move || loop {
+			let file = match fs::File::open(&msg_in) {
+				Ok(file) => file,
+				Err(_) => break,
+			};
+			for line in BufReader::new(file).lines().map_while(Result::ok) {
+				if let Some(msg) = parse_message(&line) {
+					if tx.send(msg).is_err() {
+						return;
+					}
+				}
+			}
+		});
+	}
+
+	/// Truncates and rewrites `focus_out` with the currently focused path.
+	pub fn write_focus(&self, expr: &str) -> color_eyre::Result<()> {
+		fs::write(&self.focus_out, expr)?;
+		Ok(())
+	}
+}
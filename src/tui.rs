@@ -0,0 +1,30 @@
+use std::io::{self, Stdout};
+
+use crossterm::{
+	execute,
+	terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{backend::CrosstermBackend, Terminal};
+
+pub type Tui = Terminal<CrosstermBackend<Stdout>>;
+
+pub fn init_terminal() -> color_eyre::Result<Tui> {
+	enable_raw_mode()?;
+	execute!(io::stdout(), EnterAlternateScreen)?;
+	Ok(Terminal::new(CrosstermBackend::new(io::stdout()))?)
+}
+
+pub fn restore_terminal() -> color_eyre::Result<()> {
+	execute!(io::stdout(), LeaveAlternateScreen)?;
+	disable_raw_mode()?;
+	Ok(())
+}
+
+/// Make sure the terminal is left in a sane state even if we panic mid-draw.
+pub fn install_panic_hook() {
+	let original_hook = std::panic::take_hook();
+	std::panic::set_hook(Box::new(move |panic_info| {
+		let _ = restore_terminal();
+		original_hook(panic_info);
+	}));
+}
@@ -0,0 +1,339 @@
+use std::path::PathBuf;
+
+use crossterm::event::Event;
+use kanal::Sender;
+
+use crate::{
+	model::{
+		next, prev, select_next, select_prev, Bookmark, BrowserPath, InputModel, InputState, Message,
+		Model, PathData, RunningState,
+	},
+	view::ViewData,
+};
+
+/// Everything `update` needs that isn't part of the `Model` itself: the
+/// channel used to ask the worker for a path's value, and where bookmarks
+/// persist to. Threaded through by `main` rather than stashed in `Model` so
+/// that `Model` stays plain render-affecting state.
+pub struct UpdateContext {
+	pub req_tx: Sender<BrowserPath>,
+	pub config_path: PathBuf,
+	/// Directory of the current session's `msg_in`/`focus_out`/`selection_out`
+	/// pipes, used when a request needs to write back out to them directly
+	/// rather than through the render loop's focus-change check.
+	pub session_path: PathBuf,
+}
+
+impl UpdateContext {
+	pub fn update(
+		&mut self,
+		view_data: &ViewData,
+		model: &mut Model,
+		msg: Message,
+	) -> color_eyre::Result<Option<Message>> {
+		let _ = view_data;
+
+		match msg {
+			Message::TermEvent(Event::Resize(_, _)) => {}
+			Message::TermEvent(_) => {}
+			Message::Data(path, data) => {
+				model.path_data.insert(path.clone(), data);
+				if let Some(parent) = path.parent() {
+					model.path_data.recompute_visible_for(&parent);
+				}
+				if model.pending_export
+					&& !model.selection.iter().any(|p| matches!(model.path_data.get(p), None | Some(PathData::Loading)))
+				{
+					model.pending_export = false;
+					self.export_selection(model)?;
+				}
+			}
+			Message::CurrentPath(path) => {
+				self.request(model, path.clone());
+				model.recents.retain(|p| p != &path);
+				model.recents.push(path.clone());
+				model.visit_stack.push_path(path);
+			}
+			Message::FocusPath(path) => {
+				self.request(model, path.clone());
+				model.update_parent_selection(path);
+			}
+			Message::Refresh => {
+				let stale = model.path_data.invalidate(&model.visit_stack);
+				for path in stale {
+					self.request(model, path);
+				}
+			}
+			Message::EnterItem => {
+				if let Some(current_path) = model.visit_stack.current() {
+					let selected = model
+						.path_data
+						.current_list(current_path)
+						.and_then(|list| list.selected(current_path));
+					if let Some(selected) = selected {
+						return self.update(view_data, model, Message::CurrentPath(selected));
+					}
+				}
+			}
+			Message::Back => {
+				if model.visit_stack.len() > 1 {
+					model.visit_stack.pop();
+				}
+			}
+			Message::ListDown => self.with_current_list(model, |list| {
+				list.state.select(list.state.selected().map(|i| next(i, list.len())).or(Some(0)));
+			}),
+			Message::ListUp => self.with_current_list(model, |list| {
+				list.state.select(list.state.selected().map(|i| prev(i, list.len())).or(Some(0)));
+			}),
+			Message::PageDown => self.with_current_list(model, |list| {
+				select_next(&mut list.state, list.len());
+			}),
+			Message::PageUp => self.with_current_list(model, |list| {
+				select_prev(&mut list.state, list.len());
+			}),
+			Message::SearchEnter => {
+				model.search_input = InputState::Active(InputModel {
+					typing: true,
+					input: String::new(),
+					cursor_position: 0,
+				});
+			}
+			Message::SearchExit => {
+				model.search_input = InputState::Normal;
+			}
+			Message::SearchInput(key) => {
+				if let InputState::Active(input) = &mut model.search_input {
+					input.handle_key_event(key);
+				}
+			}
+			Message::SearchNext | Message::SearchPrev => {}
+			Message::NavigatorEnter => {
+				model.path_navigator_input = InputState::Active(InputModel {
+					typing: true,
+					input: model.visit_stack.current_force().to_expr(),
+					cursor_position: 0,
+				});
+			}
+			Message::NavigatorExit => {
+				model.path_navigator_input = InputState::Normal;
+			}
+			Message::NavigatorInput(key) => {
+				if let InputState::Active(input) = &mut model.path_navigator_input {
+					input.handle_key_event(key);
+				}
+			}
+			Message::NavigatorNext | Message::NavigatorPrev => {}
+			Message::FilterEnter => {
+				let existing = self.with_current_list_ref(model, |list| list.filter.clone().unwrap_or_default());
+				model.filter_input = InputState::Active(InputModel {
+					typing: true,
+					cursor_position: existing.len(),
+					input: existing,
+				});
+			}
+			Message::FilterExit => {
+				model.filter_input = InputState::Normal;
+				self.with_current_list(model, |list| list.filter = None);
+				if let Some(current_path) = model.visit_stack.current().cloned() {
+					model.path_data.recompute_visible_for(&current_path);
+				}
+			}
+			Message::FilterInput(key) => {
+				if let InputState::Active(input) = &mut model.filter_input {
+					input.handle_key_event(key);
+				}
+				let filter = match &model.filter_input {
+					InputState::Active(input) => Some(input.input.clone()).filter(|s| !s.is_empty()),
+					InputState::Normal => None,
+				};
+				self.with_current_list(model, |list| list.filter = filter.clone());
+				if let Some(current_path) = model.visit_stack.current().cloned() {
+					model.path_data.recompute_visible_for(&current_path);
+				}
+			}
+			Message::ToggleSort => {
+				self.with_current_list(model, |list| list.sort = list.sort.next());
+				if let Some(current_path) = model.visit_stack.current().cloned() {
+					model.path_data.recompute_visible_for(&current_path);
+				}
+			}
+			Message::BookmarkInputEnter => {
+				model.new_bookmark_input = InputState::Active(InputModel {
+					typing: true,
+					input: String::new(),
+					cursor_position: 0,
+				});
+			}
+			Message::BookmarkInputExit => {
+				model.new_bookmark_input = InputState::Normal;
+			}
+			Message::BookmarkInput(key) => {
+				if let InputState::Active(input) = &mut model.new_bookmark_input {
+					input.handle_key_event(key);
+				}
+			}
+			Message::CreateBookmark(display) => {
+				let display = match display {
+					Some(display) => display,
+					None => match &model.new_bookmark_input {
+						InputState::Active(input) => input.input.clone(),
+						InputState::Normal => model.visit_stack.current_force().to_expr(),
+					},
+				};
+				model.config.bookmarks.push(Bookmark {
+					display,
+					path: model.visit_stack.current_force().clone(),
+				});
+				self.persist_config(model)?;
+				model.new_bookmark_input = InputState::Normal;
+			}
+			Message::DeleteBookmark => {
+				if let Some(i) = model.bookmark_view_state.selected() {
+					if i < model.config.bookmarks.len() {
+						model.config.bookmarks.remove(i);
+						self.persist_config(model)?;
+					}
+				}
+			}
+			Message::ToggleSelect => {
+				if let Some(current_path) = model.visit_stack.current() {
+					let selected = model
+						.path_data
+						.current_list(current_path)
+						.and_then(|list| list.selected(current_path));
+					if let Some(selected) = selected {
+						if !model.selection.shift_remove(&selected) {
+							model.selection.insert(selected);
+						}
+					}
+				}
+			}
+			Message::SelectAll => {
+				if let Some(current_path) = model.visit_stack.current().cloned() {
+					if let Some(list) = model.path_data.current_list(&current_path) {
+						let children: Vec<BrowserPath> = list
+							.list
+							.iter()
+							.map(|name| current_path.child(name.clone()))
+							.collect();
+						model.selection.extend(children);
+					}
+				}
+			}
+			Message::ClearSelection => {
+				model.selection.clear();
+			}
+			Message::ExportSelection => {
+				let missing: Vec<BrowserPath> = model
+					.selection
+					.iter()
+					.filter(|p| matches!(model.path_data.get(*p), None | Some(PathData::Loading)))
+					.cloned()
+					.collect();
+				if missing.is_empty() {
+					self.export_selection(model)?;
+				} else {
+					for path in missing {
+						self.request(model, path);
+					}
+					model.pending_export = true;
+				}
+			}
+			Message::Quit => {
+				model.running_state = RunningState::Stopped;
+			}
+		}
+
+		Ok(None)
+	}
+
+	fn request(&self, model: &mut Model, path: BrowserPath) {
+		if !model.path_data.contains_key(&path) {
+			model.path_data.insert(path.clone(), PathData::Loading);
+			let _ = self.req_tx.send(path);
+		}
+	}
+
+	fn with_current_list(&self, model: &mut Model, f: impl FnOnce(&mut crate::model::ListData)) {
+		if let Some(current_path) = model.visit_stack.current().cloned() {
+			if let Some(list) = model.path_data.current_list_mut(&current_path) {
+				f(list);
+			}
+		}
+	}
+
+	fn with_current_list_ref<T: Default>(&self, model: &Model, f: impl FnOnce(&crate::model::ListData) -> T) -> T {
+		model
+			.visit_stack
+			.current()
+			.and_then(|path| model.path_data.current_list(path))
+			.map(f)
+			.unwrap_or_default()
+	}
+
+	fn persist_config(&self, model: &Model) -> color_eyre::Result<()> {
+		let json = serde_json::to_string_pretty(&model.config)?;
+		std::fs::write(&self.config_path, json)?;
+		Ok(())
+	}
+
+	/// Serializes every marked path's resolved value into a single JSON
+	/// object keyed by its expression, writes it to the session's
+	/// `selection_out` pipe, and best-effort copies it to the system
+	/// clipboard (a missing clipboard, e.g. headless CI, is not an error).
+	fn export_selection(&self, model: &Model) -> color_eyre::Result<()> {
+		let mut object = serde_json::Map::new();
+		for path in &model.selection {
+			let value = model.path_data.get(path).map(path_data_to_json).unwrap_or(serde_json::Value::Null);
+			object.insert(path.to_expr(), value);
+		}
+
+		let json = serde_json::to_string_pretty(&object)?;
+		std::fs::write(self.session_path.join("selection_out"), &json)?;
+		copy_to_clipboard(json);
+
+		Ok(())
+	}
+}
+
+/// Scalars inline; attrsets/lists export as their member names rather than
+/// recursing, mirroring the one-level-deep laziness the rest of nix-inspect
+/// evaluates with.
+fn path_data_to_json(data: &PathData) -> serde_json::Value {
+	match data {
+		PathData::Int(i) => serde_json::json!(i),
+		PathData::Float(f) => serde_json::json!(f),
+		PathData::Bool(b) => serde_json::json!(b),
+		PathData::String(s) => serde_json::json!(s),
+		PathData::Path(p) => serde_json::json!(p),
+		PathData::Null => serde_json::Value::Null,
+		PathData::List(list) => serde_json::json!(list.list),
+		other => serde_json::json!(other.to_string()),
+	}
+}
+
+/// `arboard::Clipboard` only holds clipboard ownership on X11/Wayland for as
+/// long as the value itself is alive, so setting then immediately dropping it
+/// (as a bare `set_text` call would) can lose the copy before the user
+/// pastes. Hand it to a detached thread that blocks via `SetExtLinux::wait`
+/// until another client takes ownership, per arboard's own recommendation for
+/// short-lived Linux processes; other platforms keep clipboard contents after
+/// the setting process exits, so a plain `set_text` is enough there.
+fn copy_to_clipboard(text: String) {
+	std::thread::spawn(move || {
+		let Ok(mut clipboard) = arboard::Clipboard::new() else {
+			return;
+		};
+
+		#[cfg(target_os = "linux")]
+		{
+			use arboard::SetExtLinux;
+			let _ = clipboard.set().wait().text(text);
+		}
+		#[cfg(not(target_os = "linux"))]
+		{
+			let _ = clipboard.set_text(text);
+		}
+	});
+}
@@ -0,0 +1,89 @@
+use ratatui::{
+	layout::{Constraint, Direction, Layout, Rect},
+	style::{Modifier, Style},
+	text::Span,
+	widgets::{Block, Borders, List, ListItem, Paragraph},
+	Frame,
+};
+
+use crate::model::{InputState, Model};
+
+/// Layout rects produced by the last draw, handed back to `update`. `update`
+/// does not read them; terminal events other than keys are ignored.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ViewData {
+	pub list_area: Rect,
+	pub preview_area: Rect,
+}
+
+pub fn view(model: &Model, frame: &mut Frame) -> ViewData {
+	let chunks = Layout::default()
+		.direction(Direction::Horizontal)
+		.constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+		.split(frame.size());
+
+	let view_data = ViewData {
+		list_area: chunks[0],
+		preview_area: chunks[1],
+	};
+
+	render_list(model, frame, view_data.list_area);
+	render_preview(model, frame, view_data.preview_area);
+
+	view_data
+}
+
+fn render_list(model: &Model, frame: &mut Frame, area: Rect) {
+	let current_path = model.visit_stack.current();
+	let list_data = current_path.and_then(|p| model.path_data.current_list(p));
+
+	let items: Vec<ListItem> = list_data
+		.map(|list| {
+			list.visible
+				.iter()
+				.filter_map(|&i| list.list.get(i))
+				.map(|name| {
+					let marker = match current_path {
+						Some(path) if model.selection.contains(&path.child(name.clone())) => "* ",
+						_ => "  ",
+					};
+					ListItem::new(Span::raw(format!("{marker}{name}")))
+				})
+				.collect()
+		})
+		.unwrap_or_default();
+
+	let mut title = current_path.map(|p| p.to_expr()).unwrap_or_default();
+	if let Some(list) = list_data {
+		if let Some(filter) = &list.filter {
+			title = format!("{title} [/{filter}]");
+		}
+		title = format!("{title} ({:?})", list.sort);
+	}
+	if let InputState::Active(input) = &model.filter_input {
+		title = format!("{title} /{}", input.input);
+	}
+
+	let list = List::new(items)
+		.block(Block::default().borders(Borders::ALL).title(title))
+		.highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+	let mut state = list_data.map(|l| l.state.clone()).unwrap_or_default();
+	frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn render_preview(model: &Model, frame: &mut Frame, area: Rect) {
+	// Only highlight the lines that will actually be drawn, accounting for
+	// the surrounding border.
+	let visible_lines = 0..area.height.saturating_sub(2) as usize;
+
+	let text = model
+		.visit_stack
+		.current()
+		.and_then(|p| model.path_data.get(p))
+		.map(|data| data.highlighted(&model.config.theme, visible_lines))
+		.unwrap_or_default();
+
+	let preview = Paragraph::new(text).block(Block::default().borders(Borders::ALL).title("Preview"));
+	frame.render_widget(preview, area);
+}
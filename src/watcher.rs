@@ -0,0 +1,43 @@
+use std::{path::PathBuf, sync::mpsc, thread, time::Duration};
+
+use kanal::Sender;
+use notify::{RecursiveMode, Watcher};
+
+use crate::model::Message;
+
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Watches the files/directories backing the evaluated configuration (the
+/// `flake.nix`, `/etc/nixos`, or the `nixos-config` entry resolved in
+/// `load_config`) and emits a debounced `Message::Refresh` on the same
+/// channel key events are sent on whenever one of them changes, mirroring
+/// yazi's use of `notify` for filesystem-driven redraws.
+pub fn watch(paths: Vec<PathBuf>, tx: Sender<Message>) {
+	if paths.is_empty() {
+		return;
+	}
+
+	thread::spawn(move || {
+		let (fs_tx, fs_rx) = mpsc::channel();
+		let Ok(mut watcher) = notify::recommended_watcher(fs_tx) else {
+			return;
+		};
+		for path in &paths {
+			let _ = watcher.watch(path, RecursiveMode::Recursive);
+		}
+
+		loop {
+			// Block for the first event, then drain whatever else arrives
+			// within the debounce window so a burst of saves only triggers
+			// one re-evaluation.
+			if fs_rx.recv().is_err() {
+				break;
+			}
+			while fs_rx.recv_timeout(DEBOUNCE).is_ok() {}
+
+			if tx.send(Message::Refresh).is_err() {
+				break;
+			}
+		}
+	});
+}
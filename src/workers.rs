@@ -0,0 +1,105 @@
+use std::{process::Command, thread};
+
+use kanal::{Receiver, Sender};
+
+use crate::model::BrowserPath;
+
+#[derive(Debug, Clone)]
+pub enum NixValue {
+	Thunk,
+	Int(i64),
+	Float(f64),
+	Bool(bool),
+	String(String),
+	Path(String),
+	Null,
+	Attrs(Vec<String>),
+	List(usize),
+	Function,
+	External,
+	Error(String),
+}
+
+/// Owns the background thread that evaluates the configured Nix expression
+/// one path at a time, keeping the render loop free of blocking `nix` calls.
+pub struct WorkerHost {
+	pub tx: Sender<BrowserPath>,
+	pub rx: Receiver<(BrowserPath, NixValue)>,
+}
+
+impl WorkerHost {
+	pub fn new(expr: String) -> Self {
+		let (req_tx, req_rx) = kanal::unbounded::<BrowserPath>();
+		let (res_tx, res_rx) = kanal::unbounded::<(BrowserPath, NixValue)>();
+
+		thread::spawn(move || {
+			while let Ok(path) = req_rx.recv() {
+				let value = evaluate(&expr, &path);
+				if res_tx.send((path, value)).is_err() {
+					break;
+				}
+			}
+		});
+
+		WorkerHost { tx: req_tx, rx: res_rx }
+	}
+}
+
+/// Shells out to `nix-instantiate`, only forcing the requested path one level
+/// deep so that large attrsets don't get fully evaluated just to list them.
+fn evaluate(expr: &str, path: &BrowserPath) -> NixValue {
+	let suffix = path.to_expr();
+	let target = if suffix.is_empty() {
+		expr.to_string()
+	} else {
+		format!("({expr}).{suffix}")
+	};
+
+	let probe = format!(
+		r#"with builtins; let v = {target}; in
+		if isAttrs v then {{ type = "attrs"; names = attrNames v; }}
+		else if isList v then {{ type = "list"; length = length v; }}
+		else if isFunction v then {{ type = "function"; }}
+		else {{ type = "value"; value = v; }}"#
+	);
+
+	let output = Command::new("nix-instantiate")
+		.args(["--eval", "--json", "--strict", "--expr", &probe])
+		.output();
+
+	match output {
+		Ok(output) if output.status.success() => {
+			let stdout = String::from_utf8_lossy(&output.stdout);
+			match serde_json::from_str::<serde_json::Value>(&stdout) {
+				Ok(value) => probe_to_nix_value(value),
+				Err(e) => NixValue::Error(e.to_string()),
+			}
+		}
+		Ok(output) => NixValue::Error(String::from_utf8_lossy(&output.stderr).to_string()),
+		Err(e) => NixValue::Error(e.to_string()),
+	}
+}
+
+fn probe_to_nix_value(value: serde_json::Value) -> NixValue {
+	let ty = value.get("type").and_then(|t| t.as_str()).unwrap_or("value");
+	match ty {
+		"attrs" => NixValue::Attrs(
+			value["names"]
+				.as_array()
+				.map(|a| a.iter().filter_map(|x| x.as_str().map(str::to_string)).collect())
+				.unwrap_or_default(),
+		),
+		"list" => NixValue::List(value["length"].as_u64().unwrap_or(0) as usize),
+		"function" => NixValue::Function,
+		_ => match value.get("value") {
+			Some(serde_json::Value::Null) | None => NixValue::Null,
+			Some(serde_json::Value::Bool(b)) => NixValue::Bool(*b),
+			Some(serde_json::Value::String(s)) => NixValue::String(s.clone()),
+			Some(serde_json::Value::Number(n)) => n
+				.as_i64()
+				.map(NixValue::Int)
+				.unwrap_or_else(|| NixValue::Float(n.as_f64().unwrap_or_default())),
+			Some(other) => NixValue::Error(format!("unexpected value shape: {other}")),
+		},
+	}
+}